@@ -1,86 +1,225 @@
-use crate::data::{GpuData, GpuInfo, ProcessInfo};
-use crate::monitor::create_monitor;
+use crate::csv_log::CsvLogger;
+use crate::data::{GpuData, GpuInfo, ProcessInfo, ProcessType};
+use crate::exporter::Exporter;
+use crate::monitor::create_monitors;
 use crossbeam_channel::{Receiver, bounded};
 use eframe::egui::{self, Color32};
 use egui_plot::{Legend, Line, Plot, PlotPoints};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::{thread, time::Duration};
 
+/// Floor for the sampling interval, shared by the settings-panel slider and
+/// the CLI flag so a bogus `--interval=0` can't divide-by-zero a capacity
+/// calculation into an overflowing `usize`.
+const MIN_SAMPLE_INTERVAL_MS: u64 = 50;
+
+/// Startup defaults for sampling interval, history window and CSV logging
+/// path; overridable at runtime from the settings panel (except the path).
+pub struct RgmConfig {
+    pub sample_interval_ms: u64,
+    pub history_seconds: f64,
+    pub csv_path: String,
+}
+
+impl Default for RgmConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval_ms: 100,
+            history_seconds: 10.0,
+            csv_path: "rgm_record.csv".to_string(),
+        }
+    }
+}
+
+fn fmt_f64(value: Option<f64>) -> String {
+    value.map_or_else(|| "N/A".to_string(), |v| format!("{v:.2}"))
+}
+
+fn fmt_mhz(value: Option<u32>) -> String {
+    value.map_or_else(|| "N/A".to_string(), |v| format!("{v} MHz"))
+}
+
+fn fmt_pct(value: Option<u32>) -> String {
+    value.map_or_else(|| "N/A".to_string(), |v| format!("{v}%"))
+}
+
+// 单个 GPU 的采样状态：一个设备一个后台线程、一套环形缓冲区
+struct GpuHandle {
+    info: GpuInfo,
+    receiver: Receiver<(GpuData, Vec<ProcessInfo>)>,
+    data: Arc<Mutex<VecDeque<GpuData>>>,
+    processes: Arc<Mutex<Vec<ProcessInfo>>>,
+}
+
 // 应用程序状态
 pub struct RgmApp {
-    data: Arc<Mutex<VecDeque<GpuData>>>,
-    receiver: Receiver<(GpuData, Vec<ProcessInfo>)>,
+    gpus: Vec<GpuHandle>,
+    selected: usize,
     display_duration: f64,
-    gpu_info: GpuInfo,
-    processes: Arc<Mutex<Vec<ProcessInfo>>>,
+    sample_interval_ms: Arc<AtomicU64>,
+    csv_logger: Arc<CsvLogger>,
 }
 
 impl RgmApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        let (sender, receiver) = bounded(100);
-        let data = Arc::new(Mutex::new(VecDeque::with_capacity(120)));
-        let processes = Arc::new(Mutex::new(Vec::new()));
-
-        let monitor = create_monitor().expect("Failed to find and initialize a GPU monitor!");
-        let gpu_info = monitor.get_static_info();
-
-        thread::spawn(move || {
-            loop {
-                match monitor.sample() {
-                    Ok((gpu_data, proc_infos)) => {
-                        if sender.send((gpu_data, proc_infos)).is_err() {
-                            break;
+        Self::with_config(cc, RgmConfig::default(), None)
+    }
+
+    /// Like `new`, but with a caller-supplied config and an optional telemetry
+    /// `exporter` fed from every sample so long-running jobs can be watched
+    /// from an external dashboard too.
+    pub fn with_config(
+        cc: &eframe::CreationContext<'_>,
+        config: RgmConfig,
+        exporter: Option<Arc<Exporter>>,
+    ) -> Self {
+        let monitors = create_monitors();
+        if monitors.is_empty() {
+            panic!("Failed to find and initialize a GPU monitor!");
+        }
+
+        // Clamp to the settings panel's own floor so a bogus CLI value (e.g.
+        // `--interval=0`) can't divide-by-zero into an overflowing capacity below.
+        let sample_interval_ms_value = config.sample_interval_ms.max(MIN_SAMPLE_INTERVAL_MS);
+        let sample_interval_ms = Arc::new(AtomicU64::new(sample_interval_ms_value));
+        let csv_logger = Arc::new(CsvLogger::new(config.csv_path));
+        let history_capacity = ((config.history_seconds * 1000.0
+            / sample_interval_ms_value as f64)
+            .ceil() as usize)
+            .max(1);
+
+        let gpus = monitors
+            .into_iter()
+            .map(|monitor| {
+                let (sender, receiver) = bounded(100);
+                let data = Arc::new(Mutex::new(VecDeque::with_capacity(history_capacity)));
+                let processes = Arc::new(Mutex::new(Vec::new()));
+                let info = monitor.get_static_info();
+                let exporter = exporter.clone();
+                let csv_logger = csv_logger.clone();
+                let sample_interval_ms = sample_interval_ms.clone();
+                let thread_info = info.clone();
+
+                thread::spawn(move || {
+                    loop {
+                        match monitor.sample() {
+                            Ok((gpu_data, proc_infos)) => {
+                                if let Some(exporter) = &exporter {
+                                    exporter.report(&thread_info, &gpu_data);
+                                }
+                                csv_logger.record(thread_info.index, &thread_info.name, &gpu_data);
+                                if sender.send((gpu_data, proc_infos)).is_err() {
+                                    break;
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("Error sampling GPU data: {}", e);
+                            }
                         }
+                        thread::sleep(Duration::from_millis(
+                            sample_interval_ms.load(Ordering::Relaxed),
+                        ));
                     }
-                    Err(e) => {
-                        eprintln!("Error sampling GPU data: {}", e);
-                    }
+                });
+
+                GpuHandle {
+                    info,
+                    receiver,
+                    data,
+                    processes,
                 }
-                thread::sleep(Duration::from_millis(100));
-            }
-        });
+            })
+            .collect();
 
         let mut style = (*cc.egui_ctx.style()).clone();
         style.visuals.dark_mode = true;
         cc.egui_ctx.set_style(style);
 
         Self {
-            data,
-            receiver,
-            display_duration: 10.0,
-            gpu_info,
-            processes,
+            gpus,
+            selected: 0,
+            display_duration: config.history_seconds,
+            sample_interval_ms,
+            csv_logger,
         }
     }
 }
 
 impl eframe::App for RgmApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        while let Ok((gpu_data, proc_infos)) = self.receiver.try_recv() {
-            let mut data = self.data.lock().unwrap();
-            let now = gpu_data.timestamp;
-            let window_start_time = (now - self.display_duration).max(0.0);
-            data.push_back(gpu_data);
-            while data
-                .front()
-                .map_or(false, |d| d.timestamp < window_start_time)
-            {
-                data.pop_front();
+        let display_duration = self.display_duration;
+        for gpu in &self.gpus {
+            while let Ok((gpu_data, proc_infos)) = gpu.receiver.try_recv() {
+                let mut data = gpu.data.lock().unwrap();
+                let now = gpu_data.timestamp;
+                let window_start_time = (now - display_duration).max(0.0);
+                data.push_back(gpu_data);
+                while data
+                    .front()
+                    .map_or(false, |d| d.timestamp < window_start_time)
+                {
+                    data.pop_front();
+                }
+                let mut processes = gpu.processes.lock().unwrap();
+                *processes = proc_infos;
             }
-            let mut processes = self.processes.lock().unwrap();
-            *processes = proc_infos;
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("🚀 GPU Monitor");
-            ui.label(format!(
-                "{} - Driver: {}",
-                self.gpu_info.name, self.gpu_info.driver_version
-            ));
+
+            if self.gpus.len() > 1 {
+                ui.horizontal(|ui| {
+                    for (index, gpu) in self.gpus.iter().enumerate() {
+                        ui.selectable_value(
+                            &mut self.selected,
+                            index,
+                            format!("#{} {}", gpu.info.index, gpu.info.name),
+                        );
+                    }
+                });
+                ui.add_space(4.0);
+            }
+
+            egui::CollapsingHeader::new("⚙ Settings")
+                .default_open(false)
+                .show(ui, |ui| {
+                    let mut interval_ms = self.sample_interval_ms.load(Ordering::Relaxed);
+                    if ui
+                        .add(
+                            egui::Slider::new(&mut interval_ms, MIN_SAMPLE_INTERVAL_MS..=2000)
+                                .text("Sampling interval (ms)"),
+                        )
+                        .changed()
+                    {
+                        self.sample_interval_ms.store(
+                            interval_ms.max(MIN_SAMPLE_INTERVAL_MS),
+                            Ordering::Relaxed,
+                        );
+                    }
+
+                    ui.add(
+                        egui::Slider::new(&mut self.display_duration, 5.0..=120.0)
+                            .text("History window (s)"),
+                    );
+
+                    let mut csv_enabled = self.csv_logger.is_enabled();
+                    if ui
+                        .checkbox(&mut csv_enabled, format!("Record to {}", self.csv_logger.path()))
+                        .changed()
+                    {
+                        self.csv_logger.set_enabled(csv_enabled);
+                    }
+                });
+            ui.add_space(4.0);
+
+            let gpu = &self.gpus[self.selected];
+            ui.label(format!("{} - Driver: {}", gpu.info.name, gpu.info.driver_version));
             ui.add_space(8.0);
 
-            let data_guard = self.data.lock().unwrap();
+            let data_guard = gpu.data.lock().unwrap();
             let latest = data_guard.back();
 
             if let Some(latest) = latest {
@@ -97,7 +236,7 @@ impl eframe::App for RgmApp {
                                 .strong(),
                             );
                             ui.label(format!("Temperature: {}°C", latest.temperature));
-                            ui.label(format!("Fan Speed: {}%", latest.fan_speed));
+                            ui.label(format!("Fan Speed: {}", fmt_pct(latest.fan_speed)));
                         });
                         ui.separator();
                         ui.vertical(|ui| {
@@ -106,20 +245,34 @@ impl eframe::App for RgmApp {
                                 latest.memory_used, latest.memory_total
                             ));
                             ui.label(format!(
-                                "Power: {:.2}/{:.2} W",
-                                latest.power_usage, latest.power_limit
+                                "Power: {}/{} W",
+                                fmt_f64(latest.power_usage),
+                                fmt_f64(latest.power_limit)
                             ));
-                            ui.label(format!("GPU Clock: {} MHz", latest.gpu_clock));
-                            ui.label(format!("Memory Clock: {} MHz", latest.memory_clock));
+                            ui.label(format!("GPU Clock: {}", fmt_mhz(latest.gpu_clock)));
+                            ui.label(format!("Memory Clock: {}", fmt_mhz(latest.memory_clock)));
                         });
                         ui.separator();
                         ui.vertical(|ui| {
                             ui.label(format!(
                                 "PCIe: Gen {} x{}",
-                                self.gpu_info.pcie_gen, self.gpu_info.pcie_width
+                                gpu.info.pcie_gen, gpu.info.pcie_width
+                            ));
+                            ui.label(format!("PCIe TX: {} MB/s", fmt_f64(latest.pcie_throughput_tx)));
+                            ui.label(format!("PCIe RX: {} MB/s", fmt_f64(latest.pcie_throughput_rx)));
+                        });
+                        ui.separator();
+                        ui.vertical(|ui| {
+                            ui.label(format!("SM Clock: {}", fmt_mhz(latest.sm_clock)));
+                            ui.label(format!("Video Clock: {}", fmt_mhz(latest.video_clock)));
+                            ui.label(format!(
+                                "Encoder: {}",
+                                fmt_pct(latest.encoder_util_percent)
+                            ));
+                            ui.label(format!(
+                                "Decoder: {}",
+                                fmt_pct(latest.decoder_util_percent)
                             ));
-                            ui.label(format!("PCIe TX: {:.2} MB/s", latest.pcie_throughput_tx));
-                            ui.label(format!("PCIe RX: {:.2} MB/s", latest.pcie_throughput_rx));
                         });
                     });
                 });
@@ -127,7 +280,10 @@ impl eframe::App for RgmApp {
 
             ui.add_space(12.0);
             ui.separator();
-            ui.heading("📈 Real-time GPU Metrics (Last 10 Seconds)");
+            ui.heading(format!(
+                "📈 Real-time GPU Metrics (Last {:.0} Seconds)",
+                display_duration
+            ));
 
             let latest_timestamp = data_guard.back().map_or(0.0, |d| d.timestamp);
             let to_relative_points = |mapper: Box<dyn Fn(&GpuData) -> f64>| -> PlotPoints {
@@ -144,14 +300,30 @@ impl eframe::App for RgmApp {
             let memory_points: PlotPoints =
                 to_relative_points(Box::new(|d| d.memory_used / d.memory_total * 100.0));
             let temp_points: PlotPoints = to_relative_points(Box::new(|d| d.temperature as f64));
-            let power_points: PlotPoints = data_guard
+            let power_points: Vec<[f64; 2]> = data_guard
                 .iter()
-                .filter(|data| data.power_limit > 0.0)
-                .map(|data| {
+                .filter_map(|data| {
+                    let (usage, limit) = (data.power_usage?, data.power_limit?);
+                    if limit <= 0.0 {
+                        return None;
+                    }
                     let x = latest_timestamp - data.timestamp;
-                    [x.max(0.0), data.power_usage / data.power_limit * 100.0]
+                    Some([x.max(0.0), usage / limit * 100.0])
                 })
                 .collect();
+            let to_relative_opt_points = |mapper: fn(&GpuData) -> Option<u32>| -> Vec<[f64; 2]> {
+                data_guard
+                    .iter()
+                    .filter_map(|data| {
+                        let x = latest_timestamp - data.timestamp;
+                        Some([x.max(0.0), mapper(data)? as f64])
+                    })
+                    .collect()
+            };
+            let encoder_points = to_relative_opt_points(|d| d.encoder_util_percent);
+            let decoder_points = to_relative_opt_points(|d| d.decoder_util_percent);
+            let sm_clock_points = to_relative_opt_points(|d| d.sm_clock);
+            let video_clock_points = to_relative_opt_points(|d| d.video_clock);
 
             Plot::new("gpu_metrics_plot")
                 .view_aspect(2.5)
@@ -159,7 +331,7 @@ impl eframe::App for RgmApp {
                 .include_y(0.0)
                 .include_y(100.0)
                 .include_x(0.0)
-                .include_x(self.display_duration)
+                .include_x(display_duration)
                 .x_axis_label("Seconds Ago (0 = now)")
                 .show_x(true)
                 .show_y(true)
@@ -174,10 +346,36 @@ impl eframe::App for RgmApp {
                         Line::new("Temperature (°C)", temp_points)
                             .color(Color32::from_rgb(255, 128, 0)),
                     );
-                    plot_ui.line(
-                        Line::new("Power Usage (%)", power_points)
-                            .color(Color32::from_rgb(255, 0, 128)),
-                    );
+                    if !power_points.is_empty() {
+                        plot_ui.line(
+                            Line::new("Power Usage (%)", PlotPoints::from(power_points))
+                                .color(Color32::from_rgb(255, 0, 128)),
+                        );
+                    }
+                    if !encoder_points.is_empty() {
+                        plot_ui.line(
+                            Line::new("Encoder Utilization (%)", PlotPoints::from(encoder_points))
+                                .color(Color32::from_rgb(0, 200, 200)),
+                        );
+                    }
+                    if !decoder_points.is_empty() {
+                        plot_ui.line(
+                            Line::new("Decoder Utilization (%)", PlotPoints::from(decoder_points))
+                                .color(Color32::from_rgb(200, 200, 0)),
+                        );
+                    }
+                    if !sm_clock_points.is_empty() {
+                        plot_ui.line(
+                            Line::new("SM Clock (MHz)", PlotPoints::from(sm_clock_points))
+                                .color(Color32::from_rgb(160, 100, 255)),
+                        );
+                    }
+                    if !video_clock_points.is_empty() {
+                        plot_ui.line(
+                            Line::new("Video Clock (MHz)", PlotPoints::from(video_clock_points))
+                                .color(Color32::from_rgb(255, 160, 220)),
+                        );
+                    }
                 });
 
             ui.add_space(12.0);
@@ -186,18 +384,29 @@ impl eframe::App for RgmApp {
             egui::ScrollArea::vertical()
                 .max_height(200.0)
                 .show(ui, |ui| {
-                    let processes = self.processes.lock().unwrap();
+                    let processes = gpu.processes.lock().unwrap();
                     egui::Grid::new("processes_grid")
                         .striped(true)
                         .spacing([12.0, 6.0])
                         .show(ui, |ui| {
                             ui.label(egui::RichText::new("PID").strong());
                             ui.label(egui::RichText::new("Name").strong());
+                            ui.label(egui::RichText::new("Type").strong());
+                            ui.label(egui::RichText::new("GPU Util").strong());
                             ui.label(egui::RichText::new("Memory (MB)").strong());
                             ui.end_row();
                             for proc in processes.iter() {
                                 ui.label(proc.pid.to_string());
                                 ui.label(&proc.name);
+                                ui.label(match proc.kind {
+                                    ProcessType::Graphics => "Graphics",
+                                    ProcessType::Compute => "Compute",
+                                    ProcessType::Both => "Graphics+Compute",
+                                });
+                                ui.label(
+                                    proc.gpu_util_percent
+                                        .map_or_else(|| "N/A".to_string(), |v| format!("{v:.0}%")),
+                                );
                                 ui.label(format!(
                                     "{:.1}",
                                     proc.memory_usage as f64 / 1024.0 / 1024.0
@@ -0,0 +1,96 @@
+use crate::data::GpuData;
+use std::fs::File;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn opt<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map_or_else(String::new, |v| v.to_string())
+}
+
+/// Appends every sample (absolute timestamp + all `GpuData` fields) to a CSV
+/// file so a benchmark run can be post-processed later. Toggled on/off at
+/// runtime from the settings panel; the file is created lazily on first use.
+pub struct CsvLogger {
+    path: String,
+    enabled: AtomicBool,
+    file: Mutex<Option<File>>,
+}
+
+impl CsvLogger {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            enabled: AtomicBool::new(false),
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn record(&self, gpu_index: u32, gpu_name: &str, data: &GpuData) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut file = self.file.lock().unwrap();
+        if file.is_none() {
+            match File::create(&self.path) {
+                Ok(mut f) => {
+                    let _ = writeln!(
+                        f,
+                        "unix_time_ms,gpu_index,gpu_name,utilization,memory_used_gb,memory_total_gb,\
+                         temperature,gpu_clock,memory_clock,sm_clock,video_clock,power_usage,\
+                         power_limit,fan_speed,pcie_tx,pcie_rx,encoder_util,decoder_util"
+                    );
+                    *file = Some(f);
+                }
+                Err(e) => {
+                    eprintln!("Failed to open CSV log at {}: {e}", self.path);
+                    return;
+                }
+            }
+        }
+
+        let unix_time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+
+        if let Some(f) = file.as_mut() {
+            let _ = writeln!(
+                f,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                unix_time_ms,
+                gpu_index,
+                gpu_name,
+                data.utilization,
+                data.memory_used,
+                data.memory_total,
+                data.temperature,
+                opt(data.gpu_clock),
+                opt(data.memory_clock),
+                opt(data.sm_clock),
+                opt(data.video_clock),
+                opt(data.power_usage),
+                opt(data.power_limit),
+                opt(data.fan_speed),
+                opt(data.pcie_throughput_tx),
+                opt(data.pcie_throughput_rx),
+                opt(data.encoder_util_percent),
+                opt(data.decoder_util_percent),
+            );
+        }
+    }
+}
@@ -6,18 +6,23 @@ pub struct GpuData {
     pub memory_used: f64,
     pub memory_total: f64,
     pub temperature: u32,
-    pub gpu_clock: u32,
-    pub memory_clock: u32,
-    pub power_usage: f64,
-    pub power_limit: f64,
-    pub fan_speed: u32,
-    pub pcie_throughput_tx: f64,
-    pub pcie_throughput_rx: f64,
+    pub gpu_clock: Option<u32>,
+    pub memory_clock: Option<u32>,
+    pub sm_clock: Option<u32>,
+    pub video_clock: Option<u32>,
+    pub power_usage: Option<f64>,
+    pub power_limit: Option<f64>,
+    pub fan_speed: Option<u32>,
+    pub pcie_throughput_tx: Option<f64>,
+    pub pcie_throughput_rx: Option<f64>,
+    pub encoder_util_percent: Option<u32>,
+    pub decoder_util_percent: Option<u32>,
 }
 
 // GPU information structure, storing static information
 #[derive(Clone, Debug, Default)]
 pub struct GpuInfo {
+    pub index: u32,
     pub name: String,
     pub uuid: String,
     pub pcie_gen: u32,
@@ -26,6 +31,14 @@ pub struct GpuInfo {
     pub vbios_version: String,
 }
 
+// Whether a process was seen on the graphics queue, the compute queue, or both
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProcessType {
+    Graphics,
+    Compute,
+    Both,
+}
+
 // Process information structure, storing information about GPU processes
 #[derive(Clone, Debug)]
 pub struct ProcessInfo {
@@ -34,4 +47,6 @@ pub struct ProcessInfo {
     pub memory_usage: u64,
     #[allow(dead_code)]
     pub cpu_percent: f32,
+    pub kind: ProcessType,
+    pub gpu_util_percent: Option<f32>,
 }
@@ -0,0 +1,284 @@
+use crate::data::{GpuData, GpuInfo};
+use crossbeam_channel::{Sender, bounded};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Upper bound on a single push, so a slow or unreachable InfluxDB can hold
+/// up the push worker thread (not the sampling loop) for at most this long.
+const INFLUX_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn escape_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(' ', "\\ ")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+}
+
+/// One InfluxDB line-protocol measurement for a single sample, timestamped
+/// with wall-clock nanoseconds (not the monitor's relative `start_time`).
+///
+/// Fields the monitor couldn't read (`None`) are left out of the field set
+/// entirely, rather than fabricated as `0` — line protocol allows a variable
+/// field set per point, so there's no need to lie about an unsupported metric.
+fn to_influx_line(info: &GpuInfo, data: &GpuData) -> String {
+    let now_ns = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    line_protocol_at(info, data, now_ns)
+}
+
+fn line_protocol_at(info: &GpuInfo, data: &GpuData, timestamp_ns: u128) -> String {
+    let mut fields = vec![
+        format!("utilization={}", data.utilization),
+        format!("mem_used_gb={}", data.memory_used),
+        format!("temp={}", data.temperature),
+    ];
+    if let Some(power_usage) = data.power_usage {
+        fields.push(format!("power_w={power_usage}"));
+    }
+    if let Some(gpu_clock) = data.gpu_clock {
+        fields.push(format!("gpu_clock={gpu_clock}"));
+    }
+
+    format!(
+        "gpu,uuid={},name={} {} {}",
+        escape_tag(&info.uuid),
+        escape_tag(&info.name),
+        fields.join(","),
+        timestamp_ns,
+    )
+}
+
+/// Render the latest sample of every GPU as Prometheus exposition text.
+fn to_prometheus_text(samples: &[(GpuInfo, GpuData)]) -> String {
+    let mut out = String::new();
+    out.push_str("# HELP gpu_utilization_percent GPU core utilization.\n");
+    out.push_str("# TYPE gpu_utilization_percent gauge\n");
+    for (info, data) in samples {
+        out.push_str(&format!(
+            "gpu_utilization_percent{{uuid=\"{}\",name=\"{}\"}} {}\n",
+            info.uuid, info.name, data.utilization
+        ));
+    }
+
+    out.push_str("# HELP gpu_memory_used_bytes GPU memory in use.\n");
+    out.push_str("# TYPE gpu_memory_used_bytes gauge\n");
+    for (info, data) in samples {
+        out.push_str(&format!(
+            "gpu_memory_used_bytes{{uuid=\"{}\",name=\"{}\"}} {}\n",
+            info.uuid,
+            info.name,
+            data.memory_used * 1024.0 * 1024.0 * 1024.0
+        ));
+    }
+
+    out.push_str("# HELP gpu_temperature_celsius GPU temperature.\n");
+    out.push_str("# TYPE gpu_temperature_celsius gauge\n");
+    for (info, data) in samples {
+        out.push_str(&format!(
+            "gpu_temperature_celsius{{uuid=\"{}\",name=\"{}\"}} {}\n",
+            info.uuid, info.name, data.temperature
+        ));
+    }
+
+    if samples.iter().any(|(_, data)| data.power_usage.is_some()) {
+        out.push_str("# HELP gpu_power_watts GPU power draw.\n");
+        out.push_str("# TYPE gpu_power_watts gauge\n");
+        for (info, data) in samples {
+            if let Some(power_usage) = data.power_usage {
+                out.push_str(&format!(
+                    "gpu_power_watts{{uuid=\"{}\",name=\"{}\"}} {}\n",
+                    info.uuid, info.name, power_usage
+                ));
+            }
+        }
+    }
+
+    out
+}
+
+/// Pushes one line-protocol measurement per sample to an InfluxDB `/write` URL.
+///
+/// The actual HTTP POST happens on a dedicated worker thread so a slow or
+/// unreachable InfluxDB can't stall the sampling loop that feeds it; samples
+/// are dropped (not queued indefinitely) if the worker falls behind.
+pub struct InfluxSink {
+    sender: Sender<(GpuInfo, GpuData)>,
+}
+
+impl InfluxSink {
+    pub fn new(write_url: String) -> Self {
+        let (sender, receiver) = bounded::<(GpuInfo, GpuData)>(16);
+
+        thread::spawn(move || {
+            let agent = ureq::AgentBuilder::new()
+                .timeout(INFLUX_REQUEST_TIMEOUT)
+                .build();
+            for (info, data) in receiver.iter() {
+                let line = to_influx_line(&info, &data);
+                if let Err(e) = agent.post(&write_url).send_string(&line) {
+                    eprintln!("Failed to push metrics to InfluxDB: {e}");
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    fn push(&self, info: &GpuInfo, data: &GpuData) {
+        // try_send: if the worker is stuck behind a slow/unreachable endpoint,
+        // drop this sample instead of blocking the caller's sampling loop.
+        let _ = self.sender.try_send((info.clone(), data.clone()));
+    }
+}
+
+/// Serves the latest sample of every GPU as a Prometheus `/metrics` endpoint.
+pub struct PrometheusSink {
+    latest: Arc<Mutex<HashMap<String, (GpuInfo, GpuData)>>>,
+}
+
+impl PrometheusSink {
+    pub fn spawn(bind_addr: &str) -> Self {
+        let latest: Arc<Mutex<HashMap<String, (GpuInfo, GpuData)>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+
+        let server_latest = latest.clone();
+        let server =
+            tiny_http::Server::http(bind_addr).expect("failed to bind metrics endpoint");
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let samples: Vec<_> = server_latest.lock().unwrap().values().cloned().collect();
+                let body = to_prometheus_text(&samples);
+                let _ = request.respond(tiny_http::Response::from_string(body));
+            }
+        });
+
+        Self { latest }
+    }
+
+    fn update(&self, info: &GpuInfo, data: &GpuData) {
+        self.latest
+            .lock()
+            .unwrap()
+            .insert(info.uuid.clone(), (info.clone(), data.clone()));
+    }
+}
+
+/// A configured telemetry sink the sampling threads feed on every sample.
+pub enum Exporter {
+    Influx(InfluxSink),
+    Prometheus(PrometheusSink),
+}
+
+impl Exporter {
+    pub fn report(&self, info: &GpuInfo, data: &GpuData) {
+        match self {
+            Exporter::Influx(sink) => sink.push(info, data),
+            Exporter::Prometheus(sink) => sink.update(info, data),
+        }
+    }
+
+    /// Parse a `--export=influx:<url>` or `--export=prometheus:<bind-addr>` flag.
+    pub fn from_cli_flag(flag: &str) -> Option<Self> {
+        if let Some(url) = flag.strip_prefix("influx:") {
+            Some(Exporter::Influx(InfluxSink::new(url.to_string())))
+        } else if let Some(addr) = flag.strip_prefix("prometheus:") {
+            Some(Exporter::Prometheus(PrometheusSink::spawn(addr)))
+        } else {
+            eprintln!("Unrecognized --export value: {flag} (expected influx:<url> or prometheus:<addr>)");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_info() -> GpuInfo {
+        GpuInfo {
+            index: 0,
+            name: "RTX 4090".to_string(),
+            uuid: "GPU-abc123".to_string(),
+            pcie_gen: 4,
+            pcie_width: 16,
+            driver_version: "550.54".to_string(),
+            vbios_version: "95.02.18.80.1".to_string(),
+        }
+    }
+
+    fn sample_data() -> GpuData {
+        GpuData {
+            timestamp: 1.5,
+            utilization: 42.0,
+            memory_used: 8.0,
+            memory_total: 24.0,
+            temperature: 65,
+            gpu_clock: Some(1800),
+            memory_clock: Some(10000),
+            sm_clock: None,
+            video_clock: None,
+            power_usage: Some(250.5),
+            power_limit: Some(450.0),
+            fan_speed: Some(60),
+            pcie_throughput_tx: None,
+            pcie_throughput_rx: None,
+            encoder_util_percent: None,
+            decoder_util_percent: None,
+        }
+    }
+
+    #[test]
+    fn escape_tag_escapes_reserved_characters() {
+        assert_eq!(escape_tag("a b"), "a\\ b");
+        assert_eq!(escape_tag("a,b"), "a\\,b");
+        assert_eq!(escape_tag("a=b"), "a\\=b");
+        assert_eq!(escape_tag("a\\b"), "a\\\\b");
+        assert_eq!(escape_tag("plain"), "plain");
+    }
+
+    #[test]
+    fn line_protocol_includes_all_fields_when_present() {
+        let line = line_protocol_at(&sample_info(), &sample_data(), 1_000);
+        assert_eq!(
+            line,
+            "gpu,uuid=GPU-abc123,name=RTX\\ 4090 utilization=42,mem_used_gb=8,temp=65,power_w=250.5,gpu_clock=1800 1000"
+        );
+    }
+
+    #[test]
+    fn line_protocol_omits_unsupported_fields_instead_of_zero() {
+        let mut data = sample_data();
+        data.power_usage = None;
+        data.gpu_clock = None;
+
+        let line = line_protocol_at(&sample_info(), &data, 1_000);
+        assert_eq!(
+            line,
+            "gpu,uuid=GPU-abc123,name=RTX\\ 4090 utilization=42,mem_used_gb=8,temp=65 1000"
+        );
+        assert!(!line.contains("power_w"));
+        assert!(!line.contains("gpu_clock"));
+    }
+
+    #[test]
+    fn prometheus_text_omits_power_metric_when_no_device_reports_it() {
+        let mut data = sample_data();
+        data.power_usage = None;
+        let text = to_prometheus_text(&[(sample_info(), data)]);
+
+        assert!(text.contains("gpu_utilization_percent"));
+        assert!(text.contains("gpu_temperature_celsius"));
+        assert!(!text.contains("gpu_power_watts"));
+    }
+
+    #[test]
+    fn prometheus_text_includes_power_metric_when_reported() {
+        let text = to_prometheus_text(&[(sample_info(), sample_data())]);
+        assert!(text.contains("gpu_power_watts{uuid=\"GPU-abc123\",name=\"RTX 4090\"} 250.5"));
+    }
+}
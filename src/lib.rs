@@ -0,0 +1,6 @@
+pub mod app;
+pub mod csv_log;
+pub mod data;
+pub mod exporter;
+pub mod monitor;
+pub mod rocm;
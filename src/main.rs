@@ -1,8 +1,41 @@
 use eframe::egui::ViewportBuilder;
+use std::sync::Arc;
 
-use rgm::app::RgmApp;
+use rgm::app::{RgmApp, RgmConfig};
+use rgm::exporter::Exporter;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // --export=influx:<write-url> or --export=prometheus:<bind-addr>
+    let exporter = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix("--export=").map(str::to_string))
+        .and_then(|flag| Exporter::from_cli_flag(&flag))
+        .map(Arc::new);
+
+    let mut config = RgmConfig::default();
+    // --interval=<ms>
+    if let Some(ms) = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix("--interval="))
+        .and_then(|v| v.parse().ok())
+    {
+        config.sample_interval_ms = ms;
+    }
+    // --history=<seconds>
+    if let Some(secs) = args
+        .iter()
+        .find_map(|arg| arg.strip_prefix("--history="))
+        .and_then(|v| v.parse().ok())
+    {
+        config.history_seconds = secs;
+    }
+    // --csv=<path>
+    if let Some(path) = args.iter().find_map(|arg| arg.strip_prefix("--csv=")) {
+        config.csv_path = path.to_string();
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: ViewportBuilder::default().with_inner_size([1000.0, 700.0]),
         ..Default::default()
@@ -11,7 +44,7 @@ fn main() {
     eframe::run_native(
         "RGM",
         native_options,
-        Box::new(|cc| Ok(Box::new(RgmApp::new(cc)))),
+        Box::new(move |cc| Ok(Box::new(RgmApp::with_config(cc, config, exporter)))),
     )
     .expect("Failed to start application");
 }
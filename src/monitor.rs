@@ -1,13 +1,18 @@
-use crate::data::{GpuData, GpuInfo, ProcessInfo};
+use crate::data::{GpuData, GpuInfo, ProcessInfo, ProcessType};
 use nvml_wrapper::Nvml;
 use nvml_wrapper::enum_wrappers::device::{Clock, PcieUtilCounter, TemperatureSensor};
 use nvml_wrapper::enums::device::UsedGpuMemory;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum MonitorError {
     #[error("NVML initialization failed: {0}")]
     NvmlInit(#[from] nvml_wrapper::error::NvmlError),
+    #[error("ROCm SMI initialization failed: {0}")]
+    RocmInit(String),
     #[error("Device not found at index {0}")]
     DeviceNotFound(u32),
     #[error("Failed to get data: {0}")]
@@ -20,20 +25,25 @@ pub trait GpuMonitor: Send + Sync {
 }
 
 pub struct NvmlMonitor {
-    nvml: Nvml,
+    nvml: Arc<Nvml>,
     device_index: u32,
     start_time: std::time::Instant,
+    // NVML's process_utilization_stats() needs the timestamp (microseconds since
+    // the epoch) of the previous query to know how far back to look.
+    last_process_query_us: Mutex<Option<u64>>,
 }
 
 impl NvmlMonitor {
-    pub fn new(device_index: u32) -> Result<Self, MonitorError> {
-        let nvml = Nvml::init()?;
+    /// Build a monitor for a single device out of a shared NVML handle, so that
+    /// enumerating several GPUs doesn't re-initialize the driver once per device.
+    pub fn new(nvml: Arc<Nvml>, device_index: u32) -> Result<Self, MonitorError> {
         // 在创建时验证设备是否存在，以提前抛出错误
         nvml.device_by_index(device_index)?;
         Ok(Self {
             nvml,
             device_index,
             start_time: std::time::Instant::now(),
+            last_process_query_us: Mutex::new(None),
         })
     }
 }
@@ -44,6 +54,7 @@ impl GpuMonitor for NvmlMonitor {
         let device = self.nvml.device_by_index(self.device_index).unwrap();
 
         GpuInfo {
+            index: self.device_index,
             name: device.name().unwrap_or_else(|_| "N/A".to_string()),
             uuid: device.uuid().unwrap_or_else(|_| "N/A".to_string()),
             driver_version: self
@@ -66,23 +77,30 @@ impl GpuMonitor for NvmlMonitor {
             device.temperature(TemperatureSensor::Gpu)?,
         );
 
-        let gpu_clock = device.clock_info(Clock::Graphics).unwrap_or(0);
-        let mem_clock = device.clock_info(Clock::Memory).unwrap_or(0);
+        let gpu_clock = device.clock_info(Clock::Graphics).ok();
+        let mem_clock = device.clock_info(Clock::Memory).ok();
+        let sm_clock = device.clock_info(Clock::SM).ok();
+        let video_clock = device.clock_info(Clock::Video).ok();
+
+        let encoder_util = device.encoder_utilization().ok().map(|s| s.utilization);
+        let decoder_util = device.decoder_utilization().ok().map(|s| s.utilization);
 
         let (power_usage, power_limit) =
             match (device.power_usage(), device.power_management_limit()) {
-                (Ok(usage), Ok(limit)) => (usage as f64 / 1000.0, limit as f64 / 1000.0),
-                _ => (0.0, 0.0),
+                (Ok(usage), Ok(limit)) => {
+                    (Some(usage as f64 / 1000.0), Some(limit as f64 / 1000.0))
+                }
+                _ => (None, None),
             };
 
-        let fan_speed = device.fan_speed(0).unwrap_or(0);
+        let fan_speed = device.fan_speed(0).ok();
 
         let (pcie_tx, pcie_rx) = match (
             device.pcie_throughput(PcieUtilCounter::Send),
             device.pcie_throughput(PcieUtilCounter::Receive),
         ) {
-            (Ok(rx), Ok(tx)) => (tx as f64 / 1024.0, rx as f64 / 1024.0),
-            _ => (0.0, 0.0),
+            (Ok(tx), Ok(rx)) => (Some(tx as f64 / 1024.0), Some(rx as f64 / 1024.0)),
+            _ => (None, None),
         };
 
         let gpu_data = GpuData {
@@ -93,41 +111,139 @@ impl GpuMonitor for NvmlMonitor {
             temperature: temp,
             gpu_clock,
             memory_clock: mem_clock,
+            sm_clock,
+            video_clock,
             power_usage,
             power_limit,
             fan_speed,
             pcie_throughput_tx: pcie_tx,
             pcie_throughput_rx: pcie_rx,
+            encoder_util_percent: encoder_util,
+            decoder_util_percent: decoder_util,
         };
 
-        let mut process_infos = Vec::new();
+        let now_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+        let since_us = {
+            let mut last_query = self.last_process_query_us.lock().unwrap();
+            let since_us = last_query.unwrap_or(0);
+            *last_query = Some(now_us);
+            since_us
+        };
+        let gpu_util_by_pid: HashMap<u32, f32> = device
+            .process_utilization_stats(since_us)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|stats| (stats.pid, stats.sm_util as f32))
+            .collect();
+
+        let mut processes: HashMap<u32, ProcessInfo> = HashMap::new();
         if let Ok(procs) = device.running_graphics_processes() {
             for proc in procs {
-                let proc_name = std::fs::read_to_string(format!("/proc/{}/comm", proc.pid))
-                    .map(|s| s.trim().to_string())
-                    .unwrap_or_else(|_| "unknown".to_string());
-                let memory_usage = match proc.used_gpu_memory {
-                    UsedGpuMemory::Used(v) => v,
-                    _ => 0,
-                };
-                process_infos.push(ProcessInfo {
-                    pid: proc.pid,
-                    name: proc_name,
-                    memory_usage,
-                    cpu_percent: 0.0,
-                });
+                merge_process(&mut processes, proc, ProcessType::Graphics, &gpu_util_by_pid);
+            }
+        }
+        if let Ok(procs) = device.running_compute_processes() {
+            for proc in procs {
+                merge_process(&mut processes, proc, ProcessType::Compute, &gpu_util_by_pid);
+            }
+        }
+
+        Ok((gpu_data, processes.into_values().collect()))
+    }
+}
+
+/// Insert or update a process seen on one of NVML's graphics/compute process
+/// lists, upgrading its `kind` to `Both` if it was already seen on the other.
+fn merge_process(
+    processes: &mut HashMap<u32, ProcessInfo>,
+    proc: nvml_wrapper::struct_wrappers::device::ProcessInfo,
+    kind: ProcessType,
+    gpu_util_by_pid: &HashMap<u32, f32>,
+) {
+    use std::collections::hash_map::Entry;
+
+    let memory_usage = match proc.used_gpu_memory {
+        UsedGpuMemory::Used(v) => v,
+        _ => 0,
+    };
+    let gpu_util_percent = gpu_util_by_pid.get(&proc.pid).copied();
+
+    match processes.entry(proc.pid) {
+        Entry::Occupied(mut entry) => {
+            let info = entry.get_mut();
+            if info.kind != kind {
+                info.kind = ProcessType::Both;
             }
         }
+        Entry::Vacant(entry) => {
+            let name = std::fs::read_to_string(format!("/proc/{}/comm", proc.pid))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+            entry.insert(ProcessInfo {
+                pid: proc.pid,
+                name,
+                memory_usage,
+                cpu_percent: 0.0,
+                kind,
+                gpu_util_percent,
+            });
+        }
+    }
+}
+
+/// Enumerate every NVML-visible device and build one monitor per device so the
+/// UI can let the user switch which GPU's metrics/processes are plotted.
+fn create_nvml_monitors() -> Vec<Box<dyn GpuMonitor>> {
+    let nvml = match Nvml::init() {
+        Ok(nvml) => Arc::new(nvml),
+        Err(e) => {
+            println!("❌ NVML initialization failed: {e}");
+            return Vec::new();
+        }
+    };
+
+    let device_count = match nvml.device_count() {
+        Ok(count) => count,
+        Err(e) => {
+            println!("❌ Failed to query NVML device count: {e}");
+            return Vec::new();
+        }
+    };
 
-        Ok((gpu_data, process_infos))
+    let mut monitors: Vec<Box<dyn GpuMonitor>> = Vec::new();
+    for index in 0..device_count {
+        match NvmlMonitor::new(nvml.clone(), index) {
+            Ok(monitor) => monitors.push(Box::new(monitor)),
+            Err(e) => eprintln!("❌ Failed to initialize NVML device {index}: {e}"),
+        }
     }
+    monitors
 }
 
-pub fn create_monitor() -> Option<Box<dyn GpuMonitor>> {
-    if let Ok(monitor) = NvmlMonitor::new(0) {
-        println!("✅ NVML monitor initialized successfully.");
-        return Some(Box::new(monitor));
+/// Probe NVML first, and fall back to ROCm SMI (AMD) when no NVIDIA device
+/// answered, so the same UI works on Radeon/Instinct cards.
+pub fn create_monitors() -> Vec<Box<dyn GpuMonitor>> {
+    let monitors = create_nvml_monitors();
+    if !monitors.is_empty() {
+        println!(
+            "✅ NVML backend initialized successfully ({} device(s)).",
+            monitors.len()
+        );
+        return monitors;
+    }
+
+    let monitors = crate::rocm::create_rocm_monitors();
+    if !monitors.is_empty() {
+        println!(
+            "✅ ROCm SMI backend initialized successfully ({} device(s)).",
+            monitors.len()
+        );
+        return monitors;
     }
+
     println!("❌ No compatible GPU monitors found.");
-    None
+    monitors
 }
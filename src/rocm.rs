@@ -0,0 +1,164 @@
+use crate::data::{GpuData, GpuInfo, ProcessInfo};
+use crate::monitor::{GpuMonitor, MonitorError};
+use rocm_smi_lib::{RocmSmi, RsmiClockType, RsmiTemperatureMetric, RsmiTemperatureSensor};
+use std::sync::Arc;
+
+pub struct RocmMonitor {
+    rsmi: Arc<RocmSmi>,
+    device_index: u32,
+    start_time: std::time::Instant,
+}
+
+impl RocmMonitor {
+    /// Wrap one device index in the shared ROCm SMI handle returned by
+    /// `RocmSmi::init`, which already enumerates and talks to every AMD GPU in
+    /// the system, so each `RocmMonitor` here is just a thin view onto that
+    /// `Arc`-shared handle rather than its own driver session.
+    ///
+    /// Fails fast with `RocmInit` if the index isn't actually backed by a
+    /// device, so a bad index is caught at construction instead of on the
+    /// first `sample()` call.
+    pub fn new(rsmi: Arc<RocmSmi>, device_index: u32) -> Result<Self, MonitorError> {
+        rsmi.device_name(device_index)
+            .map_err(|e| MonitorError::RocmInit(e.to_string()))?;
+        Ok(Self {
+            rsmi,
+            device_index,
+            start_time: std::time::Instant::now(),
+        })
+    }
+}
+
+impl GpuMonitor for RocmMonitor {
+    fn get_static_info(&self) -> GpuInfo {
+        let index = self.device_index;
+
+        GpuInfo {
+            index,
+            name: self
+                .rsmi
+                .device_name(index)
+                .unwrap_or_else(|_| "N/A".to_string()),
+            uuid: self
+                .rsmi
+                .device_unique_id(index)
+                .map(|id| format!("{id:#x}"))
+                .unwrap_or_else(|_| "N/A".to_string()),
+            driver_version: self
+                .rsmi
+                .driver_version()
+                .unwrap_or_else(|_| "N/A".to_string()),
+            vbios_version: self
+                .rsmi
+                .device_vbios_version(index)
+                .unwrap_or_else(|_| "N/A".to_string()),
+            pcie_gen: self.rsmi.device_pci_gen(index).unwrap_or(0),
+            pcie_width: self.rsmi.device_pci_width(index).unwrap_or(0),
+        }
+    }
+
+    fn sample(&self) -> Result<(GpuData, Vec<ProcessInfo>), MonitorError> {
+        let index = self.device_index;
+
+        let utilization = self
+            .rsmi
+            .device_busy_percent(index)
+            .map_err(|e| MonitorError::SamplingFailed(e.to_string()))?;
+        let (mem_used, mem_total) = self
+            .rsmi
+            .device_memory_usage(index)
+            .map_err(|e| MonitorError::SamplingFailed(e.to_string()))?;
+        // rocm_smi_lib reports temperature in millidegrees Celsius, same as the
+        // underlying rsmi_dev_temp_metric_get() call.
+        let temperature_millidegrees = self
+            .rsmi
+            .device_temperature(index, RsmiTemperatureSensor::Edge, RsmiTemperatureMetric::Current)
+            .map_err(|e| MonitorError::SamplingFailed(e.to_string()))?;
+        let temperature = (temperature_millidegrees as f64 / 1000.0).round() as u32;
+
+        // rocm_smi_lib reports clocks in Hz (rsmi_dev_gpu_clk_freq_get()), so
+        // convert to MHz the same way NVML's MHz-native values are used directly.
+        let gpu_clock = self
+            .rsmi
+            .device_clock_info(index, RsmiClockType::System)
+            .ok()
+            .map(|hz| (hz as f64 / 1_000_000.0).round() as u32);
+        let mem_clock = self
+            .rsmi
+            .device_clock_info(index, RsmiClockType::Memory)
+            .ok()
+            .map(|hz| (hz as f64 / 1_000_000.0).round() as u32);
+
+        let (power_usage, power_limit) = match (
+            self.rsmi.device_power_average(index),
+            self.rsmi.device_power_cap(index),
+        ) {
+            (Ok(usage), Ok(limit)) => (
+                Some(usage as f64 / 1_000_000.0),
+                Some(limit as f64 / 1_000_000.0),
+            ),
+            _ => (None, None),
+        };
+
+        let fan_speed = self.rsmi.device_fan_speed_percent(index).ok();
+
+        let (pcie_tx, pcie_rx) = match self.rsmi.device_pci_throughput(index) {
+            Ok((tx, rx)) => (Some(tx as f64 / 1024.0), Some(rx as f64 / 1024.0)),
+            _ => (None, None),
+        };
+
+        let gpu_data = GpuData {
+            timestamp: self.start_time.elapsed().as_secs_f64(),
+            utilization: utilization as f32,
+            memory_used: mem_used as f64 / 1024.0 / 1024.0 / 1024.0,
+            memory_total: mem_total as f64 / 1024.0 / 1024.0 / 1024.0,
+            temperature,
+            gpu_clock,
+            memory_clock: mem_clock,
+            // ROCm SMI doesn't expose a separate SM/video clock domain the way
+            // NVML does.
+            sm_clock: None,
+            video_clock: None,
+            power_usage,
+            power_limit,
+            fan_speed,
+            pcie_throughput_tx: pcie_tx,
+            pcie_throughput_rx: pcie_rx,
+            // Nor does it expose VCN encoder/decoder utilization in the same shape.
+            encoder_util_percent: None,
+            decoder_util_percent: None,
+        };
+
+        // ROCm SMI doesn't expose a per-process GPU query the way NVML does, so
+        // there's nothing to populate here yet.
+        Ok((gpu_data, Vec::new()))
+    }
+}
+
+/// Enumerate every ROCm SMI-visible device and build one monitor per device.
+pub fn create_rocm_monitors() -> Vec<Box<dyn GpuMonitor>> {
+    let rsmi = match RocmSmi::init() {
+        Ok(rsmi) => Arc::new(rsmi),
+        Err(e) => {
+            println!("❌ ROCm SMI initialization failed: {e}");
+            return Vec::new();
+        }
+    };
+
+    let device_count = match rsmi.num_devices() {
+        Ok(count) => count,
+        Err(e) => {
+            println!("❌ Failed to query ROCm SMI device count: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut monitors: Vec<Box<dyn GpuMonitor>> = Vec::new();
+    for index in 0..device_count {
+        match RocmMonitor::new(rsmi.clone(), index) {
+            Ok(monitor) => monitors.push(Box::new(monitor)),
+            Err(e) => eprintln!("❌ Failed to initialize ROCm device {index}: {e}"),
+        }
+    }
+    monitors
+}